@@ -0,0 +1,42 @@
+use interpreter::lexer::{Lexer, TokenType};
+use std::hint::black_box;
+use std::time::Instant;
+
+mod source;
+
+const ITERATIONS: u32 = 10;
+
+// lexes the source until EOF, returns the number of tokens produced
+fn lex(source: &str) -> usize {
+    let mut l = Lexer::new(source.to_string());
+    let mut count = 0;
+    while l.next_token().token_type != TokenType::EOF {
+        count += 1;
+    }
+    return count;
+}
+
+/* a plain benchmark harness, reports the mean time to lex sources of growing size */
+fn main() {
+    for statements in [1_000, 10_000, 100_000] {
+        let source = source::generate_source(statements);
+        lex(&source); // warm up
+
+        let start = Instant::now();
+        let mut tokens = 0;
+        for _ in 0..ITERATIONS {
+            tokens = black_box(lex(black_box(&source)));
+        }
+        let mean = start.elapsed() / ITERATIONS;
+
+        let chars = source.chars().count();
+        println!(
+            "lex {:>7} statements ({:>8} chars, {:>7} tokens): {:>10.3?} per run, {:.1} MB/s",
+            statements,
+            chars,
+            tokens,
+            mean,
+            chars as f64 / mean.as_secs_f64() / 1e6
+        );
+    }
+}
@@ -0,0 +1,17 @@
+// Generates a synthetic Monkey source with the given number of statements, mixing every kind
+// of token the lexer handles so no single branch dominates the measurement.
+pub fn generate_source(statements: usize) -> String {
+    let mut source = String::new();
+    for i in 0..statements {
+        match i % 4 {
+            0 => source.push_str(&format!("let value_{} = {} * (x + 2.5e-3);\n", i, i)),
+            1 => source.push_str(&format!("let f_{} = fn(a, b) {{ a << b != {} }};\n", i, i)),
+            2 => source.push_str(&format!(
+                "if (f_{}(1, 2) && true) {{ return `s{}`; }}\n",
+                i, i
+            )),
+            _ => source.push_str(&format!("// comment {}\nlist[{}]?.item ?? null;\n", i, i)),
+        }
+    }
+    return source;
+}
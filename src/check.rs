@@ -0,0 +1,51 @@
+use crate::lexer::{Lexer, TokenType};
+
+// Lexes the whole source and returns a message for every diagnostic, each prefixed by its
+// line and column. The parser doesn't exist yet, so only lexer diagnostics are reported.
+pub fn check(source: &str) -> Vec<String> {
+    let mut l = Lexer::new(source.to_string());
+    while l.next_token().token_type != TokenType::EOF {}
+
+    let mut errors = vec![];
+    for diagnostic in l.diagnostics().iter() {
+        let (line, column) = line_and_column(source, diagnostic.span.start);
+        errors.push(format!(
+            "line {}, column {}: {}",
+            line, column, diagnostic.message
+        ));
+    }
+    return errors;
+}
+
+// Converts a character position into a 1-based line and column
+pub fn line_and_column(source: &str, position: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut column = 1;
+    for c in source.chars().take(position) {
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    return (line, column);
+}
+
+#[test]
+fn reports_every_error() {
+    let errors = check("let x = @;\nlet y = 5 $ 2;\nlet z = 1e;\n");
+    assert_eq!(
+        errors,
+        vec![
+            "line 1, column 9: illegal character '@'",
+            "line 2, column 11: illegal character '$'",
+            "line 3, column 9: malformed exponent in number literal 1e",
+        ]
+    );
+}
+
+#[test]
+fn clean_source() {
+    assert!(check("let add = fn(a, b) { a + b };").is_empty());
+}
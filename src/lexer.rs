@@ -0,0 +1,1947 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::io;
+use std::io::Read;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum TokenType {
+    ILLEGAL, // represents token we don't know how to parse
+    EOF,     // represents end of the source file
+
+    /* identifiers + literals */
+    ASSIGN,
+    PLUS,
+    MINUS,
+    BANG,
+    ASTERISK,
+    SLASH,
+
+    LT,
+    GT,
+
+    /* bitwise operators */
+    AMPERSAND,
+    PIPE,
+    CARET,
+
+    /* delimiters */
+    COMMA,
+    SEMICOLON,
+    COLON,
+    QUESTION,
+    DOT,
+
+    LPARAN,
+    RPARAN,
+    LBRACE,
+    RBRACE,
+    LBRACKET,
+    RBRACKET,
+
+    /* keyword */
+    FUNCTION,
+    LET,
+    TRUE,
+    FALSE,
+    IF,
+    ELSE,
+    RETURN,
+    IN,
+    MATCH,
+
+    /* identifer */
+    IDENTIFIER,
+
+    /* numbers */
+    INT,
+    FLOAT,
+
+    /* strings */
+    STRING,
+
+    /* trivia, only produced by lexers built with LexerBuilder::lossless */
+    WHITESPACE,
+    COMMENT, // a line comment, from // up to the end of the line
+
+    /* two character tokens*/
+    EQ,
+    NOT_EQ,
+    AND,
+    OR,
+    LSHIFT,
+    RSHIFT,
+    PLUSPLUS,
+    MINUSMINUS,
+    NULLISH,
+    FATARROW,
+
+    /* three character tokens */
+    ELLIPSIS,
+}
+
+// Classification of token types for tooling such as syntax highlighters and formatters
+impl TokenType {
+    // Returns true, for arithmetic, comparison, logical, bitwise and assignment operators
+    pub fn is_operator(&self) -> bool {
+        return matches!(
+            self,
+            TokenType::ASSIGN
+                | TokenType::PLUS
+                | TokenType::MINUS
+                | TokenType::BANG
+                | TokenType::ASTERISK
+                | TokenType::SLASH
+                | TokenType::LT
+                | TokenType::GT
+                | TokenType::AMPERSAND
+                | TokenType::PIPE
+                | TokenType::CARET
+                | TokenType::QUESTION
+                | TokenType::DOT
+                | TokenType::EQ
+                | TokenType::NOT_EQ
+                | TokenType::AND
+                | TokenType::OR
+                | TokenType::LSHIFT
+                | TokenType::RSHIFT
+                | TokenType::PLUSPLUS
+                | TokenType::MINUSMINUS
+                | TokenType::NULLISH
+        );
+    }
+
+    // Returns true, for reserved words. true and false are both keywords and literals
+    pub fn is_keyword(&self) -> bool {
+        return matches!(
+            self,
+            TokenType::FUNCTION
+                | TokenType::LET
+                | TokenType::TRUE
+                | TokenType::FALSE
+                | TokenType::IF
+                | TokenType::ELSE
+                | TokenType::RETURN
+                | TokenType::IN
+                | TokenType::MATCH
+        );
+    }
+
+    // Returns true, for tokens that spell out a value
+    pub fn is_literal(&self) -> bool {
+        return matches!(
+            self,
+            TokenType::INT
+                | TokenType::FLOAT
+                | TokenType::STRING
+                | TokenType::TRUE
+                | TokenType::FALSE
+        );
+    }
+
+    // Returns true, for punctuation that separates or groups other tokens
+    pub fn is_delimiter(&self) -> bool {
+        return matches!(
+            self,
+            TokenType::COMMA
+                | TokenType::SEMICOLON
+                | TokenType::COLON
+                | TokenType::LPARAN
+                | TokenType::RPARAN
+                | TokenType::LBRACE
+                | TokenType::RBRACE
+                | TokenType::LBRACKET
+                | TokenType::RBRACKET
+                | TokenType::ELLIPSIS
+                | TokenType::FATARROW
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct Token {
+    pub token_type: TokenType,
+    pub literal: String,
+    pub position: usize, // index of the token's first character in the input
+}
+
+// The characters a token covers in the input, from start up to but not including end
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn span(&self) -> Span {
+        return Span {
+            start: self.position,
+            end: self.position + self.literal.chars().count(),
+        };
+    }
+}
+
+// ANSI escape codes used by Token::pretty_print
+const KEYWORD_COLOR: &str = "\x1b[35m"; // magenta
+const LITERAL_COLOR: &str = "\x1b[33m"; // yellow
+const OPERATOR_COLOR: &str = "\x1b[36m"; // cyan
+const IDENTIFIER_COLOR: &str = "\x1b[34m"; // blue
+const ILLEGAL_COLOR: &str = "\x1b[31m"; // red
+const RESET_COLOR: &str = "\x1b[0m";
+
+impl Token {
+    // formats the token like Display, with the literal colored by its category if color is true
+    pub fn pretty_print(&self, color: bool) -> String {
+        let literal_color = if !color {
+            None
+        } else if self.token_type.is_keyword() {
+            Some(KEYWORD_COLOR) // checked before literals, so true/false print as keywords
+        } else if self.token_type.is_literal() {
+            Some(LITERAL_COLOR)
+        } else if self.token_type.is_operator() {
+            Some(OPERATOR_COLOR)
+        } else if self.token_type == TokenType::IDENTIFIER {
+            Some(IDENTIFIER_COLOR)
+        } else if self.token_type == TokenType::ILLEGAL {
+            Some(ILLEGAL_COLOR)
+        } else {
+            None
+        };
+
+        return match literal_color {
+            Some(c) => format!(
+                "{:?}, {}{}{}",
+                self.token_type, c, self.literal, RESET_COLOR
+            ),
+            None => self.to_string(),
+        };
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}, {}", self.token_type, self.literal)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    Error,   // the input isn't valid Monkey, the token is lexed as ILLEGAL
+    Warning, // the input is valid, but breaks a configured policy
+}
+
+// A problem found while lexing, the lexer still emits a recovery token for it and carries on
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub severity: Severity,
+    pub message: String,
+}
+
+// Options a Lexer is created with, every limit is off by default
+#[derive(Clone, Debug, Default)]
+struct LexerConfig {
+    max_identifier_length: Option<usize>,
+    max_number_length: Option<usize>,
+    newline_terminators: bool,
+    disallow_tabs: bool,
+    lossless: bool,
+}
+
+// Builds a Lexer with non-default options, e.g.
+// Lexer::builder().max_identifier_length(64).build(input)
+#[derive(Clone, Debug, Default)]
+pub struct LexerBuilder {
+    config: LexerConfig,
+}
+
+impl LexerBuilder {
+    pub fn new() -> LexerBuilder {
+        return LexerBuilder::default();
+    }
+
+    // identifiers longer than length characters are lexed as ILLEGAL with a diagnostic
+    pub fn max_identifier_length(mut self, length: usize) -> LexerBuilder {
+        self.config.max_identifier_length = Some(length);
+        return self;
+    }
+
+    // number literals longer than length characters are lexed as ILLEGAL with a diagnostic
+    pub fn max_number_length(mut self, length: usize) -> LexerBuilder {
+        self.config.max_number_length = Some(length);
+        return self;
+    }
+
+    // newlines that end a statement are lexed as SEMICOLON tokens (with a "\n" literal), so
+    // semicolons become optional. A newline only ends a statement when it follows a token that
+    // can end one (an identifier, literal, return, ++/--, or a closing paren/bracket/brace),
+    // isn't inside unclosed parens or brackets (unless a brace opened since, e.g. a fn body
+    // passed as an argument), and the next code after it isn't an else or a closing brace, so
+    // if/else chains, blocks and hash literals can span lines. Blank lines and
+    // newlines after an explicit semicolon or an opening brace therefore add nothing.
+    pub fn newline_terminators(mut self, enabled: bool) -> LexerBuilder {
+        self.config.newline_terminators = enabled;
+        return self;
+    }
+
+    // tabs in whitespace are reported as warnings, they are still skipped like any other
+    // whitespace. Indentation mixing tabs and spaces gets its own message, to tell it apart from
+    // a line indented with tabs only, or a tab after the start of a line.
+    pub fn disallow_tabs(mut self, enabled: bool) -> LexerBuilder {
+        self.config.disallow_tabs = enabled;
+        return self;
+    }
+
+    // whitespace and comments are lexed as WHITESPACE and COMMENT tokens instead of being
+    // skipped, for tools that must reproduce the source exactly: concatenating the literals of
+    // every token before EOF gives back the input
+    pub fn lossless(mut self, enabled: bool) -> LexerBuilder {
+        self.config.lossless = enabled;
+        return self;
+    }
+
+    pub fn build(self, input: String) -> Lexer {
+        return Lexer::from_chars(input.chars().collect(), HashMap::new(), self.config);
+    }
+
+    // Creates a Lexer over raw bytes read from reader. Invalid UTF-8 doesn't fail the read,
+    // each undecodable sequence is lexed as a U+FFFD ILLEGAL token and recorded as a diagnostic.
+    pub fn build_from_reader<R: Read>(self, mut reader: R) -> io::Result<Lexer> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+
+        let mut input = vec![];
+        let mut invalid_sequences = HashMap::new();
+        for chunk in bytes.utf8_chunks() {
+            input.extend(chunk.valid().chars());
+            if !chunk.invalid().is_empty() {
+                invalid_sequences.insert(input.len(), chunk.invalid().to_vec());
+                input.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+        return Ok(Lexer::from_chars(input, invalid_sequences, self.config));
+    }
+}
+
+pub struct Lexer {
+    config: LexerConfig,
+    input: Vec<char>, // indexed by position, so reading a character doesn't rescan the input
+    read_position: usize,
+    current_char: char,
+    lookahead_tokens: VecDeque<Token>, // tokens already lexed by lookahead, in order
+    invalid_sequences: HashMap<usize, Vec<u8>>, // undecodable bytes, by the position standing in for them
+    diagnostics: Vec<Diagnostic>,
+    last_token_type: Option<TokenType>, // type of the last token lexed, if any
+    open_delimiters: Vec<TokenType>,    // parens/brackets/braces currently open, innermost last
+    returned_end: usize,                // end of the last token next_token returned
+}
+
+//TODO: move maps and lists into constants??
+
+impl Lexer {
+    // Advances until a character that isn't whitespace or part of a comment, or the end of input is found
+    fn eat_whitespace(&mut self) {
+        loop {
+            if self.is_whitespace_char() {
+                self.read_whitespace();
+            } else if self.is_comment_start() {
+                self.read_comment_token();
+            } else {
+                return;
+            }
+        }
+    }
+
+    // Returns true, if the current character is whitespace that isn't a statement terminator
+    fn is_whitespace_char(&self) -> bool {
+        let whitespace_chars = [' ', '\t', '\n', '\r']; // whitespace characters defined in the
+                                                        // Monkey language
+        return whitespace_chars.contains(&self.current_char) && !self.at_implicit_semicolon();
+    }
+
+    // Returns true, if the current character starts a // line comment
+    fn is_comment_start(&self) -> bool {
+        return self.current_char == '/' && self.peek_char() == '/';
+    }
+
+    // returns a COMMENT token, and advances to the newline (or end of input) that ends it
+    fn read_comment_token(&mut self) -> Token {
+        let start_position = self.read_index();
+        while self.current_char != '\n' && self.read_index() < self.input.len() {
+            self.report_invalid_sequence(self.read_index());
+            self.read_char();
+        }
+        let literal: String = self.input[start_position..self.read_index()]
+            .iter()
+            .collect();
+        return Token {
+            token_type: TokenType::COMMENT,
+            literal,
+            position: start_position,
+        };
+    }
+
+    // returns a WHITESPACE token for a run of whitespace, and advances to the character after it
+    fn read_whitespace_token(&mut self) -> Token {
+        let start_position = self.read_index();
+        self.read_whitespace();
+        let literal: String = self.input[start_position..self.read_index()]
+            .iter()
+            .collect();
+        return Token {
+            token_type: TokenType::WHITESPACE,
+            literal,
+            position: start_position,
+        };
+    }
+
+    // advances past a run of whitespace, checking it for tabs if they are disallowed
+    fn read_whitespace(&mut self) {
+        let start_position = self.read_index();
+        while self.is_whitespace_char() {
+            self.read_char();
+        }
+        if self.config.disallow_tabs {
+            self.report_tabs(start_position, self.read_index());
+        }
+    }
+
+    // records a warning for every line of the whitespace from start up to end that has a tab in it
+    fn report_tabs(&mut self, start: usize, end: usize) {
+        let mut line_start = start;
+        while line_start < end {
+            let line_end = (line_start..end)
+                .find(|&i| self.input[i] == '\n')
+                .unwrap_or(end);
+            let line = &self.input[line_start..line_end];
+            if line.contains(&'\t') {
+                let is_indentation = line_start == 0 || self.input[line_start - 1] == '\n';
+                let message = if !is_indentation {
+                    "tab character"
+                } else if line.contains(&' ') {
+                    "mixed tabs and spaces in indentation"
+                } else {
+                    "tab in indentation"
+                };
+                self.report_warning(line_start, line_end, message.to_string());
+            }
+            line_start = line_end + 1;
+        }
+    }
+
+    // Lexes the rest of the input, including any tokens already buffered by lookahead, and
+    // returns the tokens. EOF isn't included.
+    pub fn into_tokens(mut self) -> Vec<Token> {
+        let mut tokens = vec![];
+        loop {
+            let t = self.next_token();
+            if t.token_type == TokenType::EOF {
+                return tokens;
+            }
+            tokens.push(t);
+        }
+    }
+
+    // Returns true, if the current character is a newline that terminates a statement,
+    // see LexerBuilder::newline_terminators
+    fn at_implicit_semicolon(&self) -> bool {
+        return self.config.newline_terminators
+            && self.current_char == '\n'
+            && matches!(self.open_delimiters.last(), None | Some(TokenType::LBRACE))
+            && matches!(
+                self.last_token_type,
+                Some(
+                    TokenType::IDENTIFIER
+                        | TokenType::INT
+                        | TokenType::FLOAT
+                        | TokenType::STRING
+                        | TokenType::TRUE
+                        | TokenType::FALSE
+                        | TokenType::RETURN
+                        | TokenType::PLUSPLUS
+                        | TokenType::MINUSMINUS
+                        | TokenType::RPARAN
+                        | TokenType::RBRACKET
+                        | TokenType::RBRACE
+                )
+            )
+            && !self.statement_continues();
+    }
+
+    // Returns true, if the code after the current newline carries on the statement before it:
+    // an else after the closing brace of an if, or the closing brace of a block or hash literal
+    fn statement_continues(&self) -> bool {
+        let next = self.skip_trivia_from(self.read_index());
+        if self.input.get(next) == Some(&'}') {
+            return true;
+        }
+        let word: String = self.input[next..]
+            .iter()
+            .take_while(|c| c.is_alphabetic() || **c == '_')
+            .collect();
+        return word == "else";
+    }
+
+    // Returns the position of the first character from position on that isn't whitespace or part of a comment
+    fn skip_trivia_from(&self, mut position: usize) -> usize {
+        loop {
+            match self.input.get(position) {
+                Some(' ' | '\t' | '\n' | '\r') => position += 1,
+                Some('/') if self.input.get(position + 1) == Some(&'/') => {
+                    while position < self.input.len() && self.input[position] != '\n' {
+                        position += 1;
+                    }
+                }
+                _ => return position,
+            }
+        }
+    }
+
+    // creates a two character token, and advances to the character after it ends
+    fn make_two_character_token(&mut self, token_type: TokenType) -> Token {
+        let position = self.read_index();
+        let literal = String::from(self.current_char) + &String::from(self.peek_char());
+        self.read_char();
+        self.read_char();
+        return Token {
+            token_type,
+            literal,
+            position,
+        };
+    }
+
+    // returns the next token, taking it from the lookahead buffer if it was already peeked
+    pub fn next_token(&mut self) -> Token {
+        let t = match self.lookahead_tokens.pop_front() {
+            Some(t) => t,
+            None => self.lex_token(),
+        };
+        self.returned_end = t.span().end;
+        return t;
+    }
+
+    // returns the token n places after the next one (0 is the token next_token would return),
+    // without consuming it. Past the end of the input this is the EOF token.
+    pub fn lookahead(&mut self, n: usize) -> &Token {
+        while self.lookahead_tokens.len() <= n {
+            let t = self.lex_token();
+            self.lookahead_tokens.push_back(t);
+        }
+        return &self.lookahead_tokens[n];
+    }
+
+    // lexes the next token from the input, guaranteeing that every token other than EOF consumes
+    // at least one character. If a branch ever fails to advance, the character it stalled on is
+    // consumed as an ILLEGAL token, so callers looping until EOF can't spin forever.
+    fn lex_token(&mut self) -> Token {
+        let mut t = self.read_token();
+        if t.token_type != TokenType::EOF && self.read_index() <= t.position {
+            let current_char = self.current_char;
+            self.read_char();
+            let message = format!("illegal character '{}'", current_char.escape_debug());
+            self.report_error(t.position, t.position + 1, message);
+            t = Token {
+                token_type: TokenType::ILLEGAL,
+                literal: String::from(current_char),
+                position: t.position,
+            };
+        }
+
+        // remember enough context to decide if the next newline ends a statement
+        match t.token_type {
+            TokenType::LPARAN | TokenType::LBRACKET | TokenType::LBRACE => {
+                self.open_delimiters.push(t.token_type.clone())
+            }
+            TokenType::RPARAN | TokenType::RBRACKET | TokenType::RBRACE => {
+                self.open_delimiters.pop();
+            }
+            _ => {}
+        }
+        if t.token_type != TokenType::WHITESPACE && t.token_type != TokenType::COMMENT {
+            self.last_token_type = Some(t.token_type.clone());
+        }
+        return t;
+    }
+
+    // Returns the index of the character the lexer will read next. Tokens already lexed by
+    // lookahead haven't been read yet as far as callers are concerned, so while there are any
+    // this is the end of the last token next_token returned.
+    pub fn position(&self) -> usize {
+        if self.lookahead_tokens.is_empty() {
+            return self.read_index();
+        }
+        return self.returned_end;
+    }
+
+    // the index of the character in current_char, past any tokens buffered by lookahead
+    fn read_index(&self) -> usize {
+        return self.read_position - 1;
+    }
+
+    // reads the next token from the input, and advances the read position to the character after it ends
+    fn read_token(&mut self) -> Token {
+        if !self.config.lossless {
+            self.eat_whitespace();
+        } else if self.is_whitespace_char() {
+            return self.read_whitespace_token();
+        } else if self.is_comment_start() {
+            return self.read_comment_token();
+        }
+        let position = self.read_index(); // position of the current character
+
+        if self.at_implicit_semicolon() {
+            self.read_char();
+            return Token {
+                token_type: TokenType::SEMICOLON,
+                literal: String::from("\n"),
+                position,
+            };
+        }
+
+        // handle everything read, by position since a '\0' in the input is just an illegal character
+        if position >= self.input.len() {
+            return Token {
+                literal: String::from(""),
+                token_type: TokenType::EOF,
+                position,
+            };
+        }
+
+        if self.report_invalid_sequence(position) {
+            let current_char = self.current_char;
+            self.read_char();
+            return Token {
+                token_type: TokenType::ILLEGAL,
+                literal: String::from(current_char),
+                position,
+            };
+        }
+
+        let single_char_token_map = HashMap::from([
+            ('=', TokenType::ASSIGN),
+            ('(', TokenType::LPARAN),
+            (')', TokenType::RPARAN),
+            ('{', TokenType::LBRACE),
+            ('}', TokenType::RBRACE),
+            ('[', TokenType::LBRACKET),
+            (']', TokenType::RBRACKET),
+            (',', TokenType::COMMA),
+            (';', TokenType::SEMICOLON),
+            (':', TokenType::COLON),
+            ('?', TokenType::QUESTION),
+            ('.', TokenType::DOT),
+            ('+', TokenType::PLUS),
+            ('-', TokenType::MINUS),
+            ('!', TokenType::BANG),
+            ('*', TokenType::ASTERISK),
+            ('/', TokenType::SLASH),
+            ('>', TokenType::GT),
+            ('<', TokenType::LT),
+            ('&', TokenType::AMPERSAND),
+            ('|', TokenType::PIPE),
+            ('^', TokenType::CARET),
+        ]);
+
+        let token_type = match single_char_token_map.get(&self.current_char) {
+            Some(t) => {
+                // I don't like this, I should compare one character than another, the shortcutting
+                // on the && so I don't unnecessarily peek characters is nice though. PROBLEM: I should only
+                // be checking for a 2nd equal on the case where I already know the current
+                // character is an equal. Here, I'm checking if the 1st character matches any of
+                // the list, then checking if the character is a equal again, then if so checking
+                // if the next character is an equal.
+                if t == &TokenType::BANG && self.peek_char() == '=' {
+                    return self.make_two_character_token(TokenType::NOT_EQ);
+                } else if t == &TokenType::ASSIGN && self.peek_char() == '=' {
+                    return self.make_two_character_token(TokenType::EQ);
+                } else if t == &TokenType::ASSIGN && self.peek_char() == '>' {
+                    return self.make_two_character_token(TokenType::FATARROW);
+                } else if t == &TokenType::AMPERSAND && self.peek_char() == '&' {
+                    return self.make_two_character_token(TokenType::AND);
+                } else if t == &TokenType::PIPE && self.peek_char() == '|' {
+                    return self.make_two_character_token(TokenType::OR);
+                } else if t == &TokenType::LT && self.peek_char() == '<' {
+                    return self.make_two_character_token(TokenType::LSHIFT);
+                } else if t == &TokenType::GT && self.peek_char() == '>' {
+                    return self.make_two_character_token(TokenType::RSHIFT);
+                } else if t == &TokenType::PLUS && self.peek_char() == '+' {
+                    return self.make_two_character_token(TokenType::PLUSPLUS);
+                } else if t == &TokenType::MINUS && self.peek_char() == '-' {
+                    return self.make_two_character_token(TokenType::MINUSMINUS);
+                } else if t == &TokenType::QUESTION && self.peek_char() == '?' {
+                    return self.make_two_character_token(TokenType::NULLISH);
+                } else if t == &TokenType::DOT
+                    && self.input[self.read_index()..].starts_with(&['.', '.', '.'])
+                {
+                    return self.read_ellipsis_token();
+                }
+                t.clone()
+            } // Clones whatever is pulled from the hashmap, this seems fine
+            None => {
+                // return early to avoid advancing an additional character
+                if self.is_identifier_char() {
+                    return self.read_identifier_token();
+                } else if self.is_number_char() {
+                    return self.read_number_token();
+                } else if self.current_char == '`' {
+                    return self.read_raw_string_token();
+                }
+                // no match found, the offending character becomes the literal and is skipped
+                // below so lexing can continue after it
+                TokenType::ILLEGAL
+            }
+        };
+
+        // store the current character I'm processing, then advance to the next character
+        let current_char = self.current_char;
+        self.read_char();
+        if token_type == TokenType::ILLEGAL {
+            let message = format!("illegal character '{}'", current_char.escape_debug());
+            self.report_error(position, position + 1, message);
+        }
+
+        return Token {
+            token_type,
+            literal: String::from(current_char),
+            position,
+        };
+    }
+
+    // returns a STRING token for a backtick-delimited raw string, and advances past its closing
+    // backtick. Nothing is escaped inside a raw string and newlines are kept as they are. The
+    // literal is the source text including the backticks, the parser strips them. A raw string
+    // that isn't closed before the end of the input is ILLEGAL.
+    fn read_raw_string_token(&mut self) -> Token {
+        let start_position = self.read_index();
+        self.read_char(); // opening backtick
+        while self.current_char != '`' && self.read_index() < self.input.len() {
+            self.report_invalid_sequence(self.read_index());
+            self.read_char();
+        }
+
+        let mut token_type = TokenType::STRING;
+        if self.current_char == '`' {
+            self.read_char(); // closing backtick
+        } else {
+            token_type = TokenType::ILLEGAL;
+            let message = String::from("unterminated raw string");
+            self.report_error(start_position, self.read_index(), message);
+        }
+
+        let literal: String = self.input[start_position..self.read_index()]
+            .iter()
+            .collect();
+        return Token {
+            token_type,
+            literal,
+            position: start_position,
+        };
+    }
+
+    // returns an ELLIPSIS token, and advances past its three dots
+    fn read_ellipsis_token(&mut self) -> Token {
+        let position = self.read_index();
+        for _ in 0..3 {
+            self.read_char();
+        }
+        return Token {
+            token_type: TokenType::ELLIPSIS,
+            literal: String::from("..."),
+            position,
+        };
+    }
+
+    /* advances to the next character in the input? */
+    fn read_char(&mut self) {
+        self.current_char = self.input.get(self.read_position).copied().unwrap_or('\0'); // '\0' stands in for the end of the input
+        self.read_position += 1;
+    }
+
+    /* return the next the next character without advancing */
+    fn peek_char(&self) -> char {
+        self.input.get(self.read_position).copied().unwrap_or('\0') // '\0' stands in for the end of the input
+    }
+
+    // side effect: advances the current_char and read_position to the end of the next identifier token
+    // returns an identifier token, if the current_char is at the begginging of an identifier token
+    fn read_identifier_token(&mut self) -> Token {
+        let start_position = self.read_position - 1; // start from the position of the current
+                                                     // character
+        while self.is_identifier_char() {
+            self.read_char();
+        }
+
+        let literal: String = self.input[start_position..self.read_position - 1]
+            .iter()
+            .collect();
+        let token_type = Lexer::lookup_identifier(&literal);
+        let token = Token {
+            literal,
+            token_type,
+            position: start_position,
+        };
+        return self.check_length(token, "identifier", self.config.max_identifier_length);
+    }
+
+    // side effect: advances the current_char and read_position to the end of the next identifier token
+    // returns a number token, if the current_char is at the beginning of a number token.
+    // Digits alone are an INT, a fraction (1.5) or an exponent (1e10, 2.5e-3, 6.02E23) make it a
+    // FLOAT. An exponent needs at least one digit after the e and optional sign, so 1e and 1e+
+    // are ILLEGAL.
+    fn read_number_token(&mut self) -> Token {
+        let start_position = self.read_position - 1;
+        let mut token_type = TokenType::INT;
+        while self.is_number_char() {
+            self.read_char();
+        }
+
+        // a fraction needs a digit after the dot, so the dot in 1.foo is left alone
+        if self.current_char == '.' && self.peek_char().is_ascii_digit() {
+            token_type = TokenType::FLOAT;
+            self.read_char();
+            while self.is_number_char() {
+                self.read_char();
+            }
+        }
+
+        let mut malformed_exponent = false;
+        if self.current_char == 'e' || self.current_char == 'E' {
+            token_type = TokenType::FLOAT;
+            self.read_char();
+            if self.current_char == '+' || self.current_char == '-' {
+                self.read_char();
+            }
+            malformed_exponent = !self.is_number_char();
+            while self.is_number_char() {
+                self.read_char();
+            }
+        }
+
+        let literal: String = self.input[start_position..self.read_position - 1]
+            .iter()
+            .collect();
+        if malformed_exponent {
+            let message = format!("malformed exponent in number literal {}", literal);
+            self.report_error(start_position, self.read_index(), message);
+            return Token {
+                literal,
+                token_type: TokenType::ILLEGAL,
+                position: start_position,
+            };
+        }
+
+        let token = Token {
+            literal,
+            token_type,
+            position: start_position,
+        };
+        return self.check_length(token, "number", self.config.max_number_length);
+    }
+
+    // turns the token into an ILLEGAL one with a diagnostic, if its literal is over the limit
+    fn check_length(&mut self, mut token: Token, kind: &str, limit: Option<usize>) -> Token {
+        let length = token.literal.chars().count();
+        if let Some(limit) = limit.filter(|limit| length > *limit) {
+            let message = format!(
+                "literal too long: {} has {} characters, the limit is {}",
+                kind, length, limit
+            );
+            self.report_error(token.position, token.position + length, message);
+            token.token_type = TokenType::ILLEGAL;
+        }
+        return token;
+    }
+
+    // Constructor for Lexer
+    pub fn new(input: String) -> Lexer {
+        return LexerBuilder::new().build(input);
+    }
+
+    // Creates a Lexer with default options over raw bytes, see LexerBuilder::build_from_reader
+    pub fn from_reader<R: Read>(reader: R) -> io::Result<Lexer> {
+        return LexerBuilder::new().build_from_reader(reader);
+    }
+
+    pub fn builder() -> LexerBuilder {
+        return LexerBuilder::new();
+    }
+
+    fn from_chars(
+        input: Vec<char>,
+        invalid_sequences: HashMap<usize, Vec<u8>>,
+        config: LexerConfig,
+    ) -> Lexer {
+        let mut l = Lexer {
+            config,
+            input,
+            read_position: 0,
+            current_char: 0 as char,
+            lookahead_tokens: VecDeque::new(),
+            invalid_sequences,
+            diagnostics: vec![],
+            last_token_type: None,
+            open_delimiters: vec![],
+            returned_end: 0,
+        };
+        l.read_char(); // primes the current character of the lexer
+        return l;
+    }
+
+    // Returns the problems found in the tokens returned so far, leaving out those of tokens
+    // lexed by lookahead until next_token returns them
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        let returned = match self.lookahead_tokens.front() {
+            Some(t) => self
+                .diagnostics
+                .partition_point(|d| d.span.start < t.position),
+            None => self.diagnostics.len(),
+        };
+        return &self.diagnostics[..returned];
+    }
+
+    // records an error covering the characters from start up to end
+    fn report_error(&mut self, start: usize, end: usize, message: String) {
+        self.diagnostics.push(Diagnostic {
+            span: Span { start, end },
+            severity: Severity::Error,
+            message,
+        });
+    }
+
+    // records an error if the character at position stands in for undecodable bytes, returns
+    // true if it did. Called wherever characters are consumed, so none slip through unreported.
+    fn report_invalid_sequence(&mut self, position: usize) -> bool {
+        let message = match self.invalid_sequences.get(&position) {
+            Some(bytes) => format!("invalid UTF-8 byte sequence {:x?}", bytes),
+            None => return false,
+        };
+        self.report_error(position, position + 1, message);
+        return true;
+    }
+
+    // records a warning covering the characters from start up to end
+    fn report_warning(&mut self, start: usize, end: usize, message: String) {
+        self.diagnostics.push(Diagnostic {
+            span: Span { start, end },
+            severity: Severity::Warning,
+            message,
+        });
+    }
+
+    // Returns true, if the current character is a valid character in an indentifier token literal
+    fn is_identifier_char(&self) -> bool {
+        return self.current_char.is_alphabetic() || self.current_char == '_';
+    }
+
+    // Returns true, if the current character is a valid character in a number token literal
+    fn is_number_char(&self) -> bool {
+        return self.current_char.is_numeric();
+    }
+
+    // return keyword TokenType, if the keyword exists otherwise returns IDENTIFIER TokenType
+    // a single match lets the compiler build the jump table instead of scanning a list
+    fn lookup_identifier(identifier: &str) -> TokenType {
+        return match identifier {
+            "fn" => TokenType::FUNCTION,
+            "let" => TokenType::LET,
+            "if" => TokenType::IF,
+            "else" => TokenType::ELSE,
+            "true" => TokenType::TRUE,
+            "false" => TokenType::FALSE,
+            "return" => TokenType::RETURN,
+            "in" => TokenType::IN,
+            "match" => TokenType::MATCH,
+            _ => TokenType::IDENTIFIER,
+        };
+    }
+
+    // Returns true, if the word is reserved as a keyword (useful for tooling, e.g. highlighters)
+    pub fn is_keyword(identifier: &str) -> bool {
+        return Lexer::lookup_identifier(identifier).is_keyword();
+    }
+}
+
+/* A helper function for test, exports all tokens from a string as a vector */
+#[cfg(test)]
+fn all_tokens_types_from_string(input: String) -> Vec<TokenType> {
+    return all_token_types(&mut Lexer::new(input));
+}
+
+/* A helper function for test, lexes up to and including EOF and returns the token types, the
+lexer is borrowed so its diagnostics can still be checked afterwards */
+#[cfg(test)]
+fn all_token_types(l: &mut Lexer) -> Vec<TokenType> {
+    let mut tokens = vec![];
+    loop {
+        tokens.push(l.next_token());
+        if tokens.iter().last().unwrap().token_type == TokenType::EOF {
+            // I put something in so I know I have atleast 1 thing to unwrap
+            break;
+        }
+    }
+
+    // TODO: Figure out how to use a map to seperate out of the token types
+    let mut token_types = vec![];
+    for token in tokens.iter() {
+        token_types.push(token.token_type.clone());
+    }
+
+    return token_types;
+}
+
+#[test]
+fn lex_compound() {
+    let correct_token_types = [
+        TokenType::FUNCTION,
+        TokenType::IDENTIFIER,
+        TokenType::LPARAN,
+        TokenType::RPARAN,
+        TokenType::LBRACE,
+        TokenType::LET,
+        TokenType::IDENTIFIER,
+        TokenType::ASSIGN,
+        TokenType::LPARAN,
+        TokenType::INT,
+        TokenType::PLUS,
+        TokenType::INT,
+        TokenType::RPARAN,
+        TokenType::SEMICOLON,
+        TokenType::RBRACE,
+        TokenType::EOF,
+    ];
+
+    let mut l = Lexer::new(String::from("fn main() { let i = (\n2 +  2    ); }"));
+
+    for correct_token in correct_token_types.into_iter() {
+        assert_eq!(l.next_token().token_type, correct_token);
+    }
+}
+
+#[test]
+fn two_char_tokens() {
+    let token_types = all_tokens_types_from_string(String::from("!====!"));
+    let token_types = dbg!(token_types);
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::NOT_EQ,
+            TokenType::EQ,
+            TokenType::ASSIGN,
+            TokenType::BANG,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn keywords() {
+    let keywords = [
+        ("fn", TokenType::FUNCTION),
+        ("let", TokenType::LET),
+        ("if", TokenType::IF),
+        ("else", TokenType::ELSE),
+        ("true", TokenType::TRUE),
+        ("false", TokenType::FALSE),
+        ("return", TokenType::RETURN),
+        ("in", TokenType::IN),
+        ("match", TokenType::MATCH),
+    ];
+    for (word, token_type) in keywords.into_iter() {
+        assert!(token_type.is_keyword()); // keeps TokenType::is_keyword in step with the words
+        assert_eq!(Lexer::lookup_identifier(word), token_type);
+        assert!(Lexer::is_keyword(word));
+    }
+
+    // near misses are plain identifiers
+    for word in [
+        "fnx", "lets", "iff", "True", "elsee", "retur", "inn", "index",
+    ] {
+        assert_eq!(Lexer::lookup_identifier(word), TokenType::IDENTIFIER);
+        assert!(!Lexer::is_keyword(word));
+    }
+}
+
+#[test]
+fn bitwise_and_logical_operators() {
+    let token_types = all_tokens_types_from_string(String::from("& && &&& | || ^ << >> < > <<<"));
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::AMPERSAND,
+            TokenType::AND,
+            TokenType::AND,
+            TokenType::AMPERSAND,
+            TokenType::PIPE,
+            TokenType::OR,
+            TokenType::CARET,
+            TokenType::LSHIFT,
+            TokenType::RSHIFT,
+            TokenType::LT,
+            TokenType::GT,
+            TokenType::LSHIFT,
+            TokenType::LT,
+            TokenType::EOF
+        ]
+    );
+
+    let mut l = Lexer::new(String::from("1 << 4"));
+    l.next_token();
+    assert_eq!(l.next_token().literal, "<<");
+}
+
+#[test]
+fn increment_and_decrement() {
+    let token_types = all_tokens_types_from_string(String::from("x++; x--; + + - - +++"));
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::IDENTIFIER,
+            TokenType::PLUSPLUS,
+            TokenType::SEMICOLON,
+            TokenType::IDENTIFIER,
+            TokenType::MINUSMINUS,
+            TokenType::SEMICOLON,
+            TokenType::PLUS,
+            TokenType::PLUS,
+            TokenType::MINUS,
+            TokenType::MINUS,
+            TokenType::PLUSPLUS,
+            TokenType::PLUS,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn in_keyword() {
+    // in is reserved, so it can no longer be used as a variable name
+    let token_types = all_tokens_types_from_string(String::from("x in arr; let in = 1;"));
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::IDENTIFIER,
+            TokenType::IN,
+            TokenType::IDENTIFIER,
+            TokenType::SEMICOLON,
+            TokenType::LET,
+            TokenType::IN,
+            TokenType::ASSIGN,
+            TokenType::INT,
+            TokenType::SEMICOLON,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn rest_parameter_tokens() {
+    let token_types = all_tokens_types_from_string(String::from("fn(first, ...rest) .. ...."));
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::FUNCTION,
+            TokenType::LPARAN,
+            TokenType::IDENTIFIER,
+            TokenType::COMMA,
+            TokenType::ELLIPSIS,
+            TokenType::IDENTIFIER,
+            TokenType::RPARAN,
+            TokenType::DOT,
+            TokenType::DOT,
+            TokenType::ELLIPSIS,
+            TokenType::DOT,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn match_expression_tokens() {
+    let token_types = all_tokens_types_from_string(String::from("match x { 1 => 10, _ => 0 }"));
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::MATCH,
+            TokenType::IDENTIFIER,
+            TokenType::LBRACE,
+            TokenType::INT,
+            TokenType::FATARROW,
+            TokenType::INT,
+            TokenType::COMMA,
+            TokenType::IDENTIFIER,
+            TokenType::FATARROW,
+            TokenType::INT,
+            TokenType::RBRACE,
+            TokenType::EOF
+        ]
+    );
+
+    // => is told apart from == and from = followed by a spaced >
+    assert_eq!(
+        all_tokens_types_from_string(String::from("=> == = > ==>")),
+        vec![
+            TokenType::FATARROW,
+            TokenType::EQ,
+            TokenType::ASSIGN,
+            TokenType::GT,
+            TokenType::EQ,
+            TokenType::GT,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn lambda_shorthand_tokens() {
+    assert_eq!(
+        all_tokens_types_from_string(String::from("((a, b) => a * b)(3, 4)")),
+        vec![
+            TokenType::LPARAN,
+            TokenType::LPARAN,
+            TokenType::IDENTIFIER,
+            TokenType::COMMA,
+            TokenType::IDENTIFIER,
+            TokenType::RPARAN,
+            TokenType::FATARROW,
+            TokenType::IDENTIFIER,
+            TokenType::ASTERISK,
+            TokenType::IDENTIFIER,
+            TokenType::RPARAN,
+            TokenType::LPARAN,
+            TokenType::INT,
+            TokenType::COMMA,
+            TokenType::INT,
+            TokenType::RPARAN,
+            TokenType::EOF
+        ]
+    );
+
+    // a parser can tell x => x + 1 from a plain identifier with one token of lookahead
+    let mut l = Lexer::new(String::from("let inc = x => x + 1;"));
+    for _ in 0..3 {
+        l.next_token(); // let inc =
+    }
+    assert_eq!(l.lookahead(0).token_type, TokenType::IDENTIFIER);
+    assert_eq!(l.lookahead(1).token_type, TokenType::FATARROW);
+    assert_eq!(l.next_token().literal, "x");
+}
+
+#[test]
+fn method_call_tokens() {
+    assert_eq!(
+        all_tokens_types_from_string(String::from("`hi`.len() [1, 2].first() x.y")),
+        vec![
+            TokenType::STRING,
+            TokenType::DOT,
+            TokenType::IDENTIFIER,
+            TokenType::LPARAN,
+            TokenType::RPARAN,
+            TokenType::LBRACKET,
+            TokenType::INT,
+            TokenType::COMMA,
+            TokenType::INT,
+            TokenType::RBRACKET,
+            TokenType::DOT,
+            TokenType::IDENTIFIER,
+            TokenType::LPARAN,
+            TokenType::RPARAN,
+            TokenType::IDENTIFIER,
+            TokenType::DOT,
+            TokenType::IDENTIFIER,
+            TokenType::EOF
+        ]
+    );
+
+    // a digit after the dot makes it part of a float instead
+    assert_eq!(
+        all_tokens_types_from_string(String::from("1.5.x")),
+        vec![
+            TokenType::FLOAT,
+            TokenType::DOT,
+            TokenType::IDENTIFIER,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn conditional_expression_tokens() {
+    let token_types = all_tokens_types_from_string(String::from("a ? b : c"));
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::IDENTIFIER,
+            TokenType::QUESTION,
+            TokenType::IDENTIFIER,
+            TokenType::COLON,
+            TokenType::IDENTIFIER,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn pretty_print() {
+    let mut l = Lexer::new(String::from("let x = 5;"));
+    let keyword = l.next_token();
+    assert_eq!(keyword.pretty_print(true), "LET, \x1b[35mlet\x1b[0m");
+    assert_eq!(keyword.pretty_print(false), "LET, let");
+
+    let identifier = l.next_token();
+    assert_eq!(
+        identifier.pretty_print(true),
+        "IDENTIFIER, \x1b[34mx\x1b[0m"
+    );
+    assert_eq!(
+        l.next_token().pretty_print(true),
+        "ASSIGN, \x1b[36m=\x1b[0m"
+    );
+    assert_eq!(l.next_token().pretty_print(true), "INT, \x1b[33m5\x1b[0m");
+    assert_eq!(l.next_token().pretty_print(true), "SEMICOLON, ;"); // delimiters stay plain
+}
+
+#[test]
+fn token_classification() {
+    let operator = TokenType::LSHIFT;
+    assert!(operator.is_operator());
+    assert!(!operator.is_keyword() && !operator.is_literal() && !operator.is_delimiter());
+
+    let keyword = TokenType::LET;
+    assert!(keyword.is_keyword());
+    assert!(!keyword.is_operator() && !keyword.is_literal() && !keyword.is_delimiter());
+
+    let literal = TokenType::INT;
+    assert!(literal.is_literal());
+    assert!(!literal.is_operator() && !literal.is_keyword() && !literal.is_delimiter());
+
+    let delimiter = TokenType::SEMICOLON;
+    assert!(delimiter.is_delimiter());
+    assert!(!delimiter.is_operator() && !delimiter.is_keyword() && !delimiter.is_literal());
+
+    assert!(TokenType::TRUE.is_keyword() && TokenType::TRUE.is_literal());
+
+    let identifier = TokenType::IDENTIFIER;
+    assert!(!identifier.is_operator() && !identifier.is_keyword());
+    assert!(!identifier.is_literal() && !identifier.is_delimiter());
+}
+
+#[test]
+fn only_illegal_characters() {
+    let input = "@#$~\\";
+    let mut l = Lexer::new(String::from(input));
+    for (i, c) in input.chars().enumerate() {
+        let t = l.next_token();
+        assert_eq!(t.token_type, TokenType::ILLEGAL);
+        assert_eq!(t.literal, String::from(c));
+        assert_eq!(t.position, i);
+        assert_eq!(l.position(), i + 1);
+        assert_eq!(
+            l.diagnostics()[i].message,
+            format!("illegal character '{}'", c.escape_debug())
+        );
+    }
+    assert_eq!(l.next_token().token_type, TokenType::EOF);
+}
+
+#[test]
+fn invalid_utf8_from_reader() {
+    // 0xC3 starts a two byte sequence, but '(' is not a continuation byte
+    let mut l = Lexer::from_reader(&b"let a = \xC3(1);"[..]).unwrap();
+    assert_eq!(
+        all_token_types(&mut l),
+        vec![
+            TokenType::LET,
+            TokenType::IDENTIFIER,
+            TokenType::ASSIGN,
+            TokenType::ILLEGAL,
+            TokenType::LPARAN,
+            TokenType::INT,
+            TokenType::RPARAN,
+            TokenType::SEMICOLON,
+            TokenType::EOF,
+        ]
+    );
+
+    assert_eq!(
+        l.diagnostics(),
+        [Diagnostic {
+            span: Span { start: 8, end: 9 },
+            severity: Severity::Error,
+            message: String::from("invalid UTF-8 byte sequence [c3]"),
+        }]
+    );
+}
+
+#[test]
+fn invalid_utf8_in_strings_and_comments() {
+    let invalid_ff = |position| Diagnostic {
+        span: Span {
+            start: position,
+            end: position + 1,
+        },
+        severity: Severity::Error,
+        message: String::from("invalid UTF-8 byte sequence [ff]"),
+    };
+
+    // the raw string is still a STRING, the bad byte is reported inside it
+    let mut l = Lexer::from_reader(&b"`a\xFFb`"[..]).unwrap();
+    let t = l.next_token();
+    assert_eq!(t.token_type, TokenType::STRING);
+    assert_eq!(t.literal, "`a\u{FFFD}b`");
+    assert_eq!(l.diagnostics(), [invalid_ff(2)]);
+
+    let mut l = Lexer::from_reader(&b"x // \xFF\ny"[..]).unwrap();
+    all_token_types(&mut l);
+    assert_eq!(l.diagnostics(), [invalid_ff(5)]);
+}
+
+#[test]
+fn nul_byte_from_reader() {
+    // an embedded NUL doesn't end the input, it is an illegal character like any other
+    let mut l = Lexer::from_reader(&b"let a = 1;\x00 let b = 2;"[..]).unwrap();
+    let token_types = all_token_types(&mut l);
+
+    assert_eq!(token_types.len(), 12);
+    assert_eq!(token_types[5], TokenType::ILLEGAL);
+    assert_eq!(token_types[10], TokenType::SEMICOLON);
+    assert_eq!(
+        l.diagnostics(),
+        [Diagnostic {
+            span: Span { start: 10, end: 11 },
+            severity: Severity::Error,
+            message: String::from("illegal character '\\0'"),
+        }]
+    );
+}
+
+#[test]
+fn float_literals() {
+    let floats = [
+        ("1e3", 1000.0),
+        ("2.5e-1", 0.25),
+        ("6.02E23", 6.02e23),
+        ("1e+2", 100.0),
+        ("12.5", 12.5),
+    ];
+    for (input, value) in floats.into_iter() {
+        let mut l = Lexer::new(String::from(input));
+        let t = l.next_token();
+        assert_eq!(t.token_type, TokenType::FLOAT);
+        assert_eq!(t.literal, input);
+        assert_eq!(t.literal.parse::<f64>().unwrap(), value);
+        assert_eq!(l.next_token().token_type, TokenType::EOF);
+    }
+
+    // a dot without a digit after it isn't part of the number
+    assert_eq!(
+        all_tokens_types_from_string(String::from("1.x")),
+        vec![
+            TokenType::INT,
+            TokenType::DOT,
+            TokenType::IDENTIFIER,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn malformed_exponents() {
+    for (input, rest) in [
+        ("1e", TokenType::EOF),
+        ("1e+", TokenType::EOF),
+        ("2E-;", TokenType::SEMICOLON),
+    ] {
+        let mut l = Lexer::new(String::from(input));
+        let t = l.next_token();
+        assert_eq!(t.token_type, TokenType::ILLEGAL);
+        assert_eq!(t.literal, input.trim_end_matches(';'));
+        assert_eq!(l.next_token().token_type, rest);
+        assert_eq!(l.diagnostics().len(), 1);
+        assert_eq!(l.diagnostics()[0].span.start, 0);
+    }
+}
+
+#[test]
+fn newline_terminators() {
+    let mut l = Lexer::builder()
+        .newline_terminators(true)
+        .build(String::from("let x = 1\nlet y = 2\n"));
+    let correct_tokens = [
+        (TokenType::LET, "let"),
+        (TokenType::IDENTIFIER, "x"),
+        (TokenType::ASSIGN, "="),
+        (TokenType::INT, "1"),
+        (TokenType::SEMICOLON, "\n"),
+        (TokenType::LET, "let"),
+        (TokenType::IDENTIFIER, "y"),
+        (TokenType::ASSIGN, "="),
+        (TokenType::INT, "2"),
+        (TokenType::SEMICOLON, "\n"),
+        (TokenType::EOF, ""),
+    ];
+    for (token_type, literal) in correct_tokens.into_iter() {
+        let t = l.next_token();
+        assert_eq!((t.token_type, t.literal.as_str()), (token_type, literal));
+    }
+}
+
+#[test]
+fn newline_terminator_edge_cases() {
+    let input = "let a = [1,\n2]\n\n\nlet f = fn(x,\ny) {\n  x;\n}\nf(a)";
+    let mut l = Lexer::builder()
+        .newline_terminators(true)
+        .build(String::from(input));
+    let token_types = all_token_types(&mut l);
+
+    assert_eq!(
+        token_types,
+        vec![
+            // newlines inside brackets don't terminate, and blank lines add nothing
+            TokenType::LET,
+            TokenType::IDENTIFIER,
+            TokenType::ASSIGN,
+            TokenType::LBRACKET,
+            TokenType::INT,
+            TokenType::COMMA,
+            TokenType::INT,
+            TokenType::RBRACKET,
+            TokenType::SEMICOLON,
+            // nor do newlines inside parens, after an opening brace or after a semicolon
+            TokenType::LET,
+            TokenType::IDENTIFIER,
+            TokenType::ASSIGN,
+            TokenType::FUNCTION,
+            TokenType::LPARAN,
+            TokenType::IDENTIFIER,
+            TokenType::COMMA,
+            TokenType::IDENTIFIER,
+            TokenType::RPARAN,
+            TokenType::LBRACE,
+            TokenType::IDENTIFIER,
+            TokenType::SEMICOLON,
+            TokenType::RBRACE,
+            TokenType::SEMICOLON,
+            TokenType::IDENTIFIER,
+            TokenType::LPARAN,
+            TokenType::IDENTIFIER,
+            TokenType::RPARAN,
+            TokenType::EOF,
+        ]
+    );
+
+    // an else or closing brace on the next line continues the statement
+    let lexer = |input: &str| {
+        return Lexer::builder()
+            .newline_terminators(true)
+            .build(String::from(input));
+    };
+    assert_eq!(
+        all_token_types(&mut lexer("if (x) {\n 1\n}\n// comment\nelse {\n 2\n}\n")),
+        vec![
+            TokenType::IF,
+            TokenType::LPARAN,
+            TokenType::IDENTIFIER,
+            TokenType::RPARAN,
+            TokenType::LBRACE,
+            TokenType::INT,
+            TokenType::RBRACE,
+            TokenType::ELSE,
+            TokenType::LBRACE,
+            TokenType::INT,
+            TokenType::RBRACE,
+            TokenType::SEMICOLON,
+            TokenType::EOF,
+        ]
+    );
+    // a block nested in parens is a statement context of its own
+    assert_eq!(
+        all_token_types(&mut lexer("f(fn() {\n x\n y\n})\n")),
+        vec![
+            TokenType::IDENTIFIER,
+            TokenType::LPARAN,
+            TokenType::FUNCTION,
+            TokenType::LPARAN,
+            TokenType::RPARAN,
+            TokenType::LBRACE,
+            TokenType::IDENTIFIER,
+            TokenType::SEMICOLON,
+            TokenType::IDENTIFIER,
+            TokenType::RBRACE,
+            TokenType::RPARAN,
+            TokenType::SEMICOLON,
+            TokenType::EOF,
+        ]
+    );
+    assert_eq!(
+        all_token_types(&mut lexer("{\n`a`: 1,\n`b`: 2\n}\nelsewhere\n")),
+        vec![
+            TokenType::LBRACE,
+            TokenType::STRING,
+            TokenType::COLON,
+            TokenType::INT,
+            TokenType::COMMA,
+            TokenType::STRING,
+            TokenType::COLON,
+            TokenType::INT,
+            TokenType::RBRACE,
+            TokenType::SEMICOLON,
+            TokenType::IDENTIFIER,
+            TokenType::SEMICOLON,
+            TokenType::EOF,
+        ]
+    );
+
+    // off by default, newlines are plain whitespace
+    assert_eq!(
+        all_tokens_types_from_string(String::from("x\ny")),
+        vec![TokenType::IDENTIFIER, TokenType::IDENTIFIER, TokenType::EOF]
+    );
+}
+
+#[test]
+fn literal_length_limits() {
+    let short = "a".repeat(10);
+    let long = "b".repeat(30);
+    let input = format!("{} {} 12345 123456", short, long);
+    let mut l = Lexer::builder()
+        .max_identifier_length(20)
+        .max_number_length(5)
+        .build(input);
+
+    let t = l.next_token();
+    assert_eq!((t.token_type, t.literal), (TokenType::IDENTIFIER, short));
+    let t = l.next_token();
+    assert_eq!((t.token_type, t.literal), (TokenType::ILLEGAL, long));
+    assert_eq!(l.next_token().token_type, TokenType::INT);
+    assert_eq!(l.next_token().token_type, TokenType::ILLEGAL);
+    assert_eq!(l.next_token().token_type, TokenType::EOF);
+
+    assert_eq!(
+        l.diagnostics(),
+        [
+            Diagnostic {
+                span: Span { start: 11, end: 41 },
+                severity: Severity::Error,
+                message: String::from(
+                    "literal too long: identifier has 30 characters, the limit is 20"
+                ),
+            },
+            Diagnostic {
+                span: Span { start: 48, end: 54 },
+                severity: Severity::Error,
+                message: String::from("literal too long: number has 6 characters, the limit is 5"),
+            },
+        ]
+    );
+
+    // no limits by default
+    let mut l = Lexer::new("c".repeat(1000));
+    assert_eq!(l.next_token().token_type, TokenType::IDENTIFIER);
+    assert!(l.diagnostics().is_empty());
+}
+
+#[test]
+fn diagnostics_are_collected() {
+    let mut l = Lexer::new(String::from("let x = 1e + @;"));
+    let token_types = all_token_types(&mut l);
+
+    // both problems still produce a token, so lexing carries on past them
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::LET,
+            TokenType::IDENTIFIER,
+            TokenType::ASSIGN,
+            TokenType::ILLEGAL,
+            TokenType::PLUS,
+            TokenType::ILLEGAL,
+            TokenType::SEMICOLON,
+            TokenType::EOF
+        ]
+    );
+    assert_eq!(
+        l.diagnostics(),
+        [
+            Diagnostic {
+                span: Span { start: 8, end: 10 },
+                severity: Severity::Error,
+                message: String::from("malformed exponent in number literal 1e"),
+            },
+            Diagnostic {
+                span: Span { start: 13, end: 14 },
+                severity: Severity::Error,
+                message: String::from("illegal character '@'"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn raw_strings() {
+    let mut l = Lexer::new(String::from("let s = `line one\nline \\n two`;"));
+    for _ in 0..3 {
+        l.next_token(); // let s =
+    }
+    let t = l.next_token();
+    assert_eq!(t.token_type, TokenType::STRING);
+    assert_eq!(t.literal, "`line one\nline \\n two`"); // the backslash-n stays two characters
+    assert_eq!(t.span(), Span { start: 8, end: 30 });
+    assert_eq!(l.next_token().token_type, TokenType::SEMICOLON);
+    assert!(l.diagnostics().is_empty());
+
+    assert_eq!(
+        all_tokens_types_from_string(String::from("``")),
+        vec![TokenType::STRING, TokenType::EOF]
+    );
+}
+
+#[test]
+fn unterminated_raw_string() {
+    let mut l = Lexer::new(String::from("x = `abc\n"));
+    l.next_token();
+    l.next_token();
+    let t = l.next_token();
+    assert_eq!(
+        (t.token_type, t.literal.as_str()),
+        (TokenType::ILLEGAL, "`abc\n")
+    );
+    assert_eq!(l.next_token().token_type, TokenType::EOF);
+    assert_eq!(l.diagnostics()[0].span, Span { start: 4, end: 9 });
+    assert_eq!(l.diagnostics()[0].message, "unterminated raw string");
+}
+
+#[test]
+fn comments_are_skipped() {
+    assert_eq!(
+        all_tokens_types_from_string(String::from("x // a comment\n/ y // trailing")),
+        vec![
+            TokenType::IDENTIFIER,
+            TokenType::SLASH,
+            TokenType::IDENTIFIER,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn lossless_tokens() {
+    let input = "let  x=5; // five\n\n\tfn(a,b){a+b}   // add\r\n  `raw\n str` @";
+    let tokens = Lexer::builder()
+        .lossless(true)
+        .build(String::from(input))
+        .into_tokens();
+
+    let reconstructed: String = tokens.iter().map(|t| t.literal.as_str()).collect();
+    assert_eq!(reconstructed, input);
+
+    let trivia: Vec<(TokenType, &str)> = tokens
+        .iter()
+        .filter(|t| t.token_type == TokenType::WHITESPACE || t.token_type == TokenType::COMMENT)
+        .map(|t| (t.token_type.clone(), t.literal.as_str()))
+        .take(4)
+        .collect();
+    assert_eq!(
+        trivia,
+        vec![
+            (TokenType::WHITESPACE, "  "),
+            (TokenType::WHITESPACE, " "),
+            (TokenType::COMMENT, "// five"),
+            (TokenType::WHITESPACE, "\n\n\t"),
+        ]
+    );
+
+    // NULs are ordinary characters, in code, comments and raw strings alike
+    for input in ["a\0b", "x // a\0b\n`c\0d` \0"] {
+        let tokens = Lexer::builder()
+            .lossless(true)
+            .build(String::from(input))
+            .into_tokens();
+        let reconstructed: String = tokens.iter().map(|t| t.literal.as_str()).collect();
+        assert_eq!(reconstructed, input);
+    }
+
+    // tokens looked ahead at are lexed losslessly too, so they keep their trivia
+    let mut l = Lexer::builder().lossless(true).build(String::from("a  b"));
+    assert_eq!(l.lookahead(1).token_type, TokenType::WHITESPACE);
+    let literals: Vec<String> = l.into_tokens().into_iter().map(|t| t.literal).collect();
+    assert_eq!(literals, ["a", "  ", "b"]);
+}
+
+#[test]
+fn lossless_newline_terminators() {
+    let input = "let x = 1 // one\nx\n";
+    let l = Lexer::builder()
+        .newline_terminators(true)
+        .lossless(true)
+        .build(String::from(input));
+    let tokens = l.into_tokens();
+
+    let reconstructed: String = tokens.iter().map(|t| t.literal.as_str()).collect();
+    assert_eq!(reconstructed, input);
+    assert_eq!(
+        tokens
+            .iter()
+            .filter(|t| t.token_type == TokenType::SEMICOLON)
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn disallowed_tabs() {
+    let input = "let x = 1;\n\tx;\n  \tx;\n    x; \t// c\n";
+    let warning = |start, end, message: &str| Diagnostic {
+        span: Span { start, end },
+        severity: Severity::Warning,
+        message: message.to_string(),
+    };
+
+    let mut l = Lexer::builder()
+        .disallow_tabs(true)
+        .build(String::from(input));
+    all_token_types(&mut l);
+    assert_eq!(
+        l.diagnostics(),
+        [
+            warning(11, 12, "tab in indentation"),
+            warning(15, 18, "mixed tabs and spaces in indentation"),
+            warning(27, 29, "tab character"),
+        ]
+    );
+
+    // tabs are plain whitespace by default, and are still kept as whitespace when disallowed
+    let mut l = Lexer::new(String::from(input));
+    all_token_types(&mut l);
+    assert!(l.diagnostics().is_empty());
+    let l = Lexer::builder()
+        .disallow_tabs(true)
+        .lossless(true)
+        .build(String::from("\tx"));
+    assert_eq!(l.into_tokens()[0].token_type, TokenType::WHITESPACE);
+}
+
+#[test]
+fn non_ascii_identifiers() {
+    let mut l = Lexer::new(String::from("élan + ñ"));
+    assert_eq!(l.next_token().literal, "élan");
+    assert_eq!(l.next_token().token_type, TokenType::PLUS);
+    let t = l.next_token();
+    assert_eq!(t.literal, "ñ");
+    assert_eq!(t.position, 7);
+}
+
+#[test]
+fn lookahead() {
+    let mut l = Lexer::new(String::from("a + b"));
+    assert_eq!(l.lookahead(2).literal, "b");
+    assert_eq!(l.lookahead(0).literal, "a");
+    assert_eq!(l.lookahead(5).token_type, TokenType::EOF);
+
+    // peeked tokens are still returned in order, and don't move the position until they are
+    assert_eq!(l.position(), 0);
+    assert_eq!(l.next_token().literal, "a");
+    assert_eq!(l.position(), 1);
+    assert_eq!(l.next_token().token_type, TokenType::PLUS);
+    assert_eq!(l.lookahead(0).literal, "b");
+    assert_eq!(l.next_token().literal, "b");
+    assert_eq!(l.next_token().token_type, TokenType::EOF);
+    assert_eq!(l.next_token().token_type, TokenType::EOF);
+
+    // nor do their diagnostics show up before they are returned
+    let mut l = Lexer::new(String::from("a @ c"));
+    assert_eq!(l.lookahead(2).literal, "c");
+    assert_eq!(l.position(), 0);
+    assert!(l.diagnostics().is_empty());
+    l.next_token();
+    assert!(l.diagnostics().is_empty());
+    assert_eq!(l.next_token().token_type, TokenType::ILLEGAL);
+    assert_eq!(l.diagnostics().len(), 1);
+    assert_eq!(l.position(), 3);
+    l.next_token();
+    assert_eq!(l.position(), 5);
+    assert_eq!(l.lookahead(0).token_type, TokenType::EOF);
+    assert_eq!(l.position(), 5);
+}
+
+#[test]
+fn null_coalescing_operator() {
+    let token_types = all_tokens_types_from_string(String::from("a ?? b ? c : d ? ? ???"));
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::IDENTIFIER,
+            TokenType::NULLISH,
+            TokenType::IDENTIFIER,
+            TokenType::QUESTION,
+            TokenType::IDENTIFIER,
+            TokenType::COLON,
+            TokenType::IDENTIFIER,
+            TokenType::QUESTION,
+            TokenType::QUESTION,
+            TokenType::NULLISH,
+            TokenType::QUESTION,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn safe_index_tokens() {
+    let token_types = all_tokens_types_from_string(String::from("h?[0]?[1] [a]"));
+    assert_eq!(
+        token_types,
+        vec![
+            TokenType::IDENTIFIER,
+            TokenType::QUESTION,
+            TokenType::LBRACKET,
+            TokenType::INT,
+            TokenType::RBRACKET,
+            TokenType::QUESTION,
+            TokenType::LBRACKET,
+            TokenType::INT,
+            TokenType::RBRACKET,
+            TokenType::LBRACKET,
+            TokenType::IDENTIFIER,
+            TokenType::RBRACKET,
+            TokenType::EOF
+        ]
+    );
+}
+
+#[test]
+fn two_char_token_literals() {
+    let mut l = Lexer::new(String::from("a != b"));
+    l.next_token();
+    let t = l.next_token();
+    assert_eq!(t.token_type, TokenType::NOT_EQ);
+    assert_eq!(t.literal, "!=");
+    assert_eq!(t.position, 2);
+}
+
+#[test]
+fn illegal_character() {
+    let mut l = Lexer::new(String::from("let x = @ + 5;"));
+    for _ in 0..3 {
+        l.next_token(); // let x =
+    }
+
+    let illegal = l.next_token();
+    assert_eq!(illegal.token_type, TokenType::ILLEGAL);
+    assert_eq!(illegal.literal, "@");
+    assert_eq!(illegal.position, 8);
+
+    // lexing continues after the illegal character
+    let rest = [
+        (TokenType::PLUS, "+"),
+        (TokenType::INT, "5"),
+        (TokenType::SEMICOLON, ";"),
+        (TokenType::EOF, ""),
+    ];
+    for (token_type, literal) in rest.into_iter() {
+        let t = l.next_token();
+        assert_eq!(t.token_type, token_type);
+        assert_eq!(t.literal, literal);
+    }
+}
+
+/* TODO: Test case for whitespace between characters on two character tokens */
@@ -0,0 +1,5 @@
+pub mod check;
+pub mod lexer;
+pub mod repl;
+pub mod semantic;
+pub mod stats;
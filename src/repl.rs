@@ -0,0 +1,226 @@
+use crate::lexer::{Lexer, TokenType};
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{BufRead, ErrorKind, Write};
+use std::path::PathBuf;
+
+const PROMPT: &str = ">>";
+const CONTINUATION_PROMPT: &str = "... ";
+
+// Lines entered into the REPL, optionally persisted to a file so they survive restarts
+pub struct History {
+    file: Option<File>, // the history file new lines are appended to, if there is one
+    lines: Vec<String>,
+}
+
+impl History {
+    // Constructor for a History that is only kept in memory
+    pub fn new() -> History {
+        return History {
+            file: None,
+            lines: vec![],
+        };
+    }
+
+    // Creates a History backed by the file at path, loading any lines already saved there.
+    // A missing file is created as an empty history. Fails if the file can't be read or opened
+    // for appending, so a bad path is found before the REPL starts rather than on the first line.
+    pub fn load(path: PathBuf) -> io::Result<History> {
+        let lines = match fs::read_to_string(&path) {
+            Ok(contents) => contents.lines().map(String::from).collect(),
+            Err(e) if e.kind() == ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e),
+        };
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        return Ok(History {
+            file: Some(file),
+            lines,
+        });
+    }
+
+    pub fn lines(&self) -> &[String] {
+        return &self.lines;
+    }
+
+    // records a line, and appends it to the history file if there is one. If writing to the
+    // file fails the error is reported once, and the history is only kept in memory from then on.
+    fn add(&mut self, line: &str) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            return;
+        }
+
+        if let Some(file) = &mut self.file {
+            if let Err(e) = writeln!(file, "{}", line) {
+                eprintln!("history is no longer saved: {}", e);
+                self.file = None;
+            }
+        }
+        self.lines.push(line.to_string());
+    }
+}
+
+impl Default for History {
+    fn default() -> History {
+        return History::new();
+    }
+}
+
+/* a simple REPL, outputs the tokens created by each complete input, in color if color is true */
+pub fn start<R: BufRead, W: Write>(
+    mut input: R,
+    mut output: W,
+    history: &mut History,
+    color: bool,
+) {
+    loop {
+        write!(output, "{}", PROMPT).unwrap();
+        output.flush().unwrap();
+
+        let mut source = String::new();
+        if input.read_line(&mut source).unwrap() == 0 {
+            return; // end of input
+        }
+        history.add(&source);
+
+        // keep reading lines until every opened paren/brace/bracket has been closed
+        while is_incomplete(&source) {
+            write!(output, "{}", CONTINUATION_PROMPT).unwrap();
+            output.flush().unwrap();
+            let mut line = String::new();
+            if input.read_line(&mut line).unwrap() == 0 {
+                break; // dump what we have, the input ended mid expression
+            }
+            history.add(&line);
+            source.push_str(&line);
+        }
+
+        let mut l = Lexer::new(source);
+        let mut t = l.next_token();
+        while t.token_type != TokenType::EOF {
+            writeln!(output, "{}", t.pretty_print(color)).unwrap();
+            t = l.next_token();
+        }
+    }
+}
+
+// Returns true, if the input opens more parens/braces/brackets than it closes, or ends in the
+// middle of a raw string
+fn is_incomplete(source: &str) -> bool {
+    let mut depth: i64 = 0;
+    let mut in_raw_string = false;
+    let mut l = Lexer::new(source.to_string());
+    loop {
+        let t = l.next_token();
+        match t.token_type {
+            TokenType::LPARAN | TokenType::LBRACE | TokenType::LBRACKET => depth += 1,
+            TokenType::RPARAN | TokenType::RBRACE | TokenType::RBRACKET => depth -= 1,
+            TokenType::EOF => break,
+            _ => {}
+        }
+        // an unterminated raw string runs to the end of the input, so it is always the last token
+        in_raw_string = t.token_type == TokenType::ILLEGAL && t.literal.starts_with('`');
+    }
+    return depth > 0 || in_raw_string;
+}
+
+/* A helper function for test, runs the REPL over a script and returns everything it wrote */
+#[cfg(test)]
+fn run_script(script: &str) -> String {
+    let mut output = vec![];
+    start(script.as_bytes(), &mut output, &mut History::new(), false);
+    return String::from_utf8(output).unwrap();
+}
+
+#[test]
+fn single_line_input() {
+    assert_eq!(run_script("x;\n"), ">>IDENTIFIER, x\nSEMICOLON, ;\n>>");
+}
+
+#[test]
+fn multi_line_input() {
+    let output = run_script("let f = fn(x) {\n  x\n};\n");
+    assert_eq!(
+        output,
+        [
+            ">>... ... LET, let",
+            "IDENTIFIER, f",
+            "ASSIGN, =",
+            "FUNCTION, fn",
+            "LPARAN, (",
+            "IDENTIFIER, x",
+            "RPARAN, )",
+            "LBRACE, {",
+            "IDENTIFIER, x",
+            "RBRACE, }",
+            "SEMICOLON, ;",
+            ">>",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn unbalanced_parens_across_lines() {
+    assert!(is_incomplete("add(1,\n"));
+    assert!(!is_incomplete("add(1,\n2)\n"));
+    assert!(is_incomplete("[1,\n"));
+    assert!(!is_incomplete("}\n")); // extra closers are left for the parser to report
+    assert!(is_incomplete("let s = `one\n"));
+    assert!(!is_incomplete("let s = `one\ntwo`;\n"));
+}
+
+#[test]
+fn multi_line_raw_string() {
+    assert_eq!(
+        run_script("let s = `one\ntwo`;\n"),
+        ">>... LET, let\nIDENTIFIER, s\nASSIGN, =\nSTRING, `one\ntwo`\nSEMICOLON, ;\n>>"
+    );
+}
+
+#[test]
+fn history_persists_across_sessions() {
+    let path = std::env::temp_dir().join(format!("monkey-history-{}", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let mut history = History::load(path.clone()).unwrap();
+    start(
+        "let a = 1;\n\nfn(x) {\nx }\n".as_bytes(),
+        vec![],
+        &mut history,
+        false,
+    );
+
+    // reopening the REPL loads the earlier lines, then keeps appending to them
+    let mut history = History::load(path.clone()).unwrap();
+    assert_eq!(history.lines(), ["let a = 1;", "fn(x) {", "x }"]);
+    start("a;\n".as_bytes(), vec![], &mut history, false);
+    let history = History::load(path.clone()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(history.lines(), ["let a = 1;", "fn(x) {", "x }", "a;"]);
+}
+
+#[test]
+fn colored_output() {
+    let mut output = vec![];
+    start("x\n".as_bytes(), &mut output, &mut History::new(), true);
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        ">>IDENTIFIER, \x1b[34mx\x1b[0m\n>>"
+    );
+}
+
+#[test]
+fn unusable_history_path() {
+    let missing_dir = std::env::temp_dir().join(format!("monkey-missing-{}", std::process::id()));
+    assert_eq!(
+        History::load(missing_dir.join("history"))
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::NotFound
+    );
+    assert!(History::load(std::env::temp_dir()).is_err()); // a directory, not a file
+}
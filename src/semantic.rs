@@ -0,0 +1,90 @@
+use crate::lexer::{Lexer, Span, TokenType};
+
+// LSP-style kinds assigned to tokens for semantic highlighting
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SemanticKind {
+    Keyword,
+    Number,
+    String,
+    Operator,
+    Identifier,
+    Comment,
+}
+
+// Lexes the input and returns the span and kind of every token an editor should highlight,
+// including comments. Whitespace, delimiters and illegal characters are left out.
+pub fn semantic_tokens(input: &str) -> Vec<(Span, SemanticKind)> {
+    let mut tokens = vec![];
+    let l = Lexer::builder().lossless(true).build(input.to_string());
+    for t in l.into_tokens() {
+        if let Some(kind) = semantic_kind(&t.token_type) {
+            tokens.push((t.span(), kind));
+        }
+    }
+    return tokens;
+}
+
+fn semantic_kind(token_type: &TokenType) -> Option<SemanticKind> {
+    if token_type.is_keyword() {
+        return Some(SemanticKind::Keyword);
+    } else if token_type.is_operator() {
+        return Some(SemanticKind::Operator);
+    }
+    return match token_type {
+        TokenType::INT | TokenType::FLOAT => Some(SemanticKind::Number),
+        TokenType::STRING => Some(SemanticKind::String),
+        TokenType::IDENTIFIER => Some(SemanticKind::Identifier),
+        TokenType::COMMENT => Some(SemanticKind::Comment),
+        _ => None,
+    };
+}
+
+#[test]
+fn semantic_kinds() {
+    let tokens = semantic_tokens("let x = 10 + y;");
+    assert_eq!(
+        tokens,
+        vec![
+            (Span { start: 0, end: 3 }, SemanticKind::Keyword),
+            (Span { start: 4, end: 5 }, SemanticKind::Identifier),
+            (Span { start: 6, end: 7 }, SemanticKind::Operator),
+            (Span { start: 8, end: 10 }, SemanticKind::Number),
+            (Span { start: 11, end: 12 }, SemanticKind::Operator),
+            (Span { start: 13, end: 14 }, SemanticKind::Identifier),
+        ]
+    );
+}
+
+#[test]
+fn strings() {
+    let tokens = semantic_tokens("let s = `hi`;");
+    assert_eq!(tokens[0].1, SemanticKind::Keyword);
+    assert_eq!(
+        tokens[3],
+        (Span { start: 8, end: 12 }, SemanticKind::String)
+    );
+}
+
+#[test]
+fn comments() {
+    let tokens = semantic_tokens("x; // note\ny");
+    assert_eq!(
+        tokens,
+        vec![
+            (Span { start: 0, end: 1 }, SemanticKind::Identifier),
+            (Span { start: 3, end: 10 }, SemanticKind::Comment),
+            (Span { start: 11, end: 12 }, SemanticKind::Identifier),
+        ]
+    );
+}
+
+#[test]
+fn booleans_are_keywords() {
+    let tokens = semantic_tokens("true != false");
+    assert_eq!(tokens[0].1, SemanticKind::Keyword);
+    assert_eq!(
+        tokens[1],
+        (Span { start: 5, end: 7 }, SemanticKind::Operator)
+    );
+    assert_eq!(tokens[2].1, SemanticKind::Keyword);
+}
@@ -0,0 +1,66 @@
+use crate::lexer::{Lexer, TokenType};
+use std::collections::HashMap;
+
+// Simple code metrics gathered from a full lex pass over a Monkey source
+#[derive(Debug, Default)]
+pub struct TokenStats {
+    pub counts: HashMap<TokenType, usize>, // number of tokens of each type, EOF is not counted
+    pub lines: usize,
+    pub identifiers: usize,
+    pub keywords: usize,
+}
+
+impl TokenStats {
+    // returns how many tokens of the given type were lexed
+    pub fn count(&self, token_type: &TokenType) -> usize {
+        return self.counts.get(token_type).copied().unwrap_or(0);
+    }
+
+    // returns the number of tokens lexed, EOF is not counted
+    pub fn total(&self) -> usize {
+        return self.counts.values().sum();
+    }
+}
+
+pub fn token_stats(input: &str) -> TokenStats {
+    let mut stats = TokenStats {
+        lines: input.lines().count(),
+        ..TokenStats::default()
+    };
+
+    let mut l = Lexer::new(input.to_string());
+    loop {
+        let t = l.next_token();
+        if t.token_type == TokenType::EOF {
+            break;
+        }
+
+        if t.token_type == TokenType::IDENTIFIER {
+            stats.identifiers += 1;
+        } else if t.token_type.is_keyword() {
+            stats.keywords += 1;
+        }
+        *stats.counts.entry(t.token_type).or_insert(0) += 1;
+    }
+    return stats;
+}
+
+#[test]
+fn counts_tokens() {
+    let stats = token_stats("let five = 5;\nlet add = fn(a, b) {\n  return a + b + 10;\n};\n");
+    assert_eq!(stats.lines, 4);
+    assert_eq!(stats.keywords, 4); // let, let, fn, return
+    assert_eq!(stats.identifiers, 6); // five, add, a, b, a, b
+    assert_eq!(stats.count(&TokenType::INT), 2);
+    assert_eq!(stats.count(&TokenType::LET), 2);
+    assert_eq!(stats.count(&TokenType::SEMICOLON), 3);
+    assert_eq!(stats.count(&TokenType::EOF), 0);
+    assert_eq!(stats.total(), 24);
+}
+
+#[test]
+fn empty_input() {
+    let stats = token_stats("");
+    assert_eq!(stats.lines, 0);
+    assert_eq!(stats.total(), 0);
+}
@@ -0,0 +1,75 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/* A helper function for test, writes the source to a temporary file */
+fn write_source(name: &str, source: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("{}-{}.monkey", name, std::process::id()));
+    fs::write(&path, source).unwrap();
+    return path;
+}
+
+fn run(args: &[&str]) -> Output {
+    return Command::new(env!("CARGO_BIN_EXE_interpreter"))
+        .args(args)
+        .output()
+        .unwrap();
+}
+
+#[test]
+fn check_reports_all_errors() {
+    let path = write_source("check-errors", "let x = @;\nlet y = 5 $ 2;\n");
+    let output = run(&["--check", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(stderr.contains("line 1, column 9: illegal character '@'"));
+    assert!(stderr.contains("line 2, column 11: illegal character '$'"));
+}
+
+#[test]
+fn check_accepts_clean_file() {
+    let path = write_source("check-clean", "let x = 5;\n");
+    let output = run(&["--check", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn color_is_plain_when_piped() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_interpreter"))
+        .arg("--color")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"let x = 5;\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("LET, let"));
+    assert!(!stdout.contains('\x1b'));
+}
+
+#[test]
+fn unusable_history_path_is_rejected() {
+    let path = env::temp_dir()
+        .join(format!("monkey-missing-{}", std::process::id()))
+        .join("history");
+    let output = run(&["--repl-history", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("No such file"));
+}
@@ -0,0 +1,38 @@
+use interpreter::lexer::{Lexer, TokenType};
+use std::time::{Duration, Instant};
+
+#[path = "../benches/source.rs"]
+mod source;
+
+fn time_to_lex(source: &str) -> Duration {
+    let start = Instant::now();
+    let mut l = Lexer::new(source.to_string());
+    while l.next_token().token_type != TokenType::EOF {}
+    return start.elapsed();
+}
+
+// Guards against the O(n²) lexer coming back (e.g. read_char walking the input with
+// chars().nth): four times the input should take about four times as long, a quadratic lexer
+// would take sixteen times as long. The sizes are timed in alternating rounds keeping the
+// fastest of each, so a burst of load on the machine slows both rather than failing the test.
+#[test]
+fn lexing_scales_linearly() {
+    let small = source::generate_source(500);
+    let large = source::generate_source(2_000);
+
+    let mut small_time = Duration::MAX;
+    let mut large_time = Duration::MAX;
+    for _ in 0..7 {
+        small_time = small_time.min(time_to_lex(&small));
+        large_time = large_time.min(time_to_lex(&large));
+    }
+
+    let ratio = large_time.as_secs_f64() / small_time.as_secs_f64();
+    assert!(
+        ratio < 8.0,
+        "lexing four times the input took {:.2}x as long ({:?} vs {:?})",
+        ratio,
+        large_time,
+        small_time
+    );
+}